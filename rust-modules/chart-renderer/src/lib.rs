@@ -73,31 +73,62 @@ impl ChartProcessor {
     /// Downsample data using LTTB (Largest Triangle Three Buckets) algorithm
     /// This is useful for rendering large datasets efficiently
     pub fn downsample(&self, target_points: usize) -> Float32Array {
-        if target_points >= self.data.len() {
+        if target_points >= self.data.len() || target_points <= 2 {
+            if target_points <= 2 && !self.data.is_empty() {
+                let first = self.data[0];
+                let last = *self.data.last().unwrap();
+                return Float32Array::from(&[first.x, first.y, last.x, last.y][..]);
+            }
             // No need to downsample
             return self.get_chunk(0);
         }
 
         let mut result = Vec::with_capacity(target_points * 2);
-        let bucket_size = (self.data.len() as f32) / (target_points as f32);
+        // Buckets cover the data excluding the fixed first/last points.
+        let bucket_size = ((self.data.len() - 2) as f32) / ((target_points - 2) as f32);
 
         // Always include first point
         result.push(self.data[0].x);
         result.push(self.data[0].y);
 
-        // Simple downsampling (average within buckets)
-        // For production, implement LTTB algorithm for better visual representation
-        for i in 1..target_points - 1 {
-            let start = (i as f32 * bucket_size) as usize;
-            let end = ((i + 1) as f32 * bucket_size) as usize;
-            
-            let avg_x: f32 = self.data[start..end].iter().map(|p| p.x).sum::<f32>() 
-                / (end - start) as f32;
-            let avg_y: f32 = self.data[start..end].iter().map(|p| p.y).sum::<f32>() 
-                / (end - start) as f32;
-            
-            result.push(avg_x);
-            result.push(avg_y);
+        let mut a = self.data[0];
+
+        for i in 0..target_points - 2 {
+            let bucket_start = 1 + (i as f32 * bucket_size) as usize;
+            let bucket_end = (1 + ((i + 1) as f32 * bucket_size) as usize).min(self.data.len() - 1);
+            if bucket_start >= bucket_end {
+                continue;
+            }
+
+            // Average point of the next bucket (or the genuine last point for the final bucket).
+            let c = if i == target_points - 3 {
+                let last = self.data.last().unwrap();
+                DataPoint { x: last.x, y: last.y }
+            } else {
+                let next_start = bucket_end;
+                let next_end = (1 + ((i + 2) as f32 * bucket_size) as usize).min(self.data.len() - 1);
+                let next_end = next_end.max(next_start + 1);
+                let next_bucket = &self.data[next_start..next_end];
+                let avg_x = next_bucket.iter().map(|p| p.x).sum::<f32>() / next_bucket.len() as f32;
+                let avg_y = next_bucket.iter().map(|p| p.y).sum::<f32>() / next_bucket.len() as f32;
+                DataPoint { x: avg_x, y: avg_y }
+            };
+
+            let mut best_area = -1.0f32;
+            let mut best_point = self.data[bucket_start];
+
+            for &b in &self.data[bucket_start..bucket_end] {
+                let area = 0.5
+                    * ((a.x - c.x) * (b.y - a.y) - (a.x - b.x) * (c.y - a.y)).abs();
+                if area > best_area {
+                    best_area = area;
+                    best_point = b;
+                }
+            }
+
+            result.push(best_point.x);
+            result.push(best_point.y);
+            a = best_point;
         }
 
         // Always include last point
@@ -132,8 +163,10 @@ impl ChartProcessor {
 }
 
 // Extension points:
-// 1. Implement proper LTTB (Largest Triangle Three Buckets) algorithm
-// 2. Add support for SharedArrayBuffer for zero-copy transfers
-// 3. Implement streaming updates for real-time data
-// 4. Add WebGL rendering helpers
-// 5. Implement windowing for zooming/panning operations
+// 1. Add support for SharedArrayBuffer for zero-copy transfers
+// 2. Implement streaming updates for real-time data
+// 3. Add WebGL rendering helpers
+// 4. Implement windowing for zooming/panning operations
+// 5. Add a spill-to-IndexedDB external mode (see `data-engine`'s `spill`
+//    module) once `ChartProcessor` loads real series instead of generating
+//    sample data in its constructor
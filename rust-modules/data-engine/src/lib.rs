@@ -1,6 +1,18 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Cursor;
+
+use arrow::array::{Array, Float64Array, StringArray, UInt32Array};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+mod spill;
+
+use spill::SpillManager;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global allocator
 #[cfg(feature = "wee_alloc")]
@@ -31,10 +43,348 @@ pub struct AggregateResult {
     pub avg_sales: f64,
 }
 
+/// Combinable per-category aggregation state for a single chunk.
+///
+/// Variance is tracked with Welford's online algorithm so that partials
+/// computed independently (e.g. in separate Web Worker chunks) can later be
+/// merged without re-scanning the underlying rows.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryAccumulator {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub m2: f64,
+}
+
+impl CategoryAccumulator {
+    fn from_value(value: f64) -> Self {
+        Self {
+            count: 1,
+            sum: value,
+            min: value,
+            max: value,
+            mean: value,
+            m2: 0.0,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        let delta2 = value - self.mean;
+        self.m2 += delta * delta2;
+    }
+
+    /// Merge another accumulator's state into this one using the parallel
+    /// variance formula, so chunk-level partials combine without rescanning.
+    fn merge(&mut self, other: &CategoryAccumulator) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        let n_a = self.count as f64;
+        let n_b = other.count as f64;
+        let n = n_a + n_b;
+        let delta = other.mean - self.mean;
+
+        self.mean += delta * n_b / n;
+        self.m2 += other.m2 + delta * delta * n_a * n_b / n;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+    }
+
+    fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+}
+
+/// Combinable intermediate aggregation state produced over one chunk of rows.
+/// Many of these, computed independently (e.g. one per Web Worker), can be
+/// folded together via [`DataEngine::merge_intermediates`] without
+/// re-scanning the original rows.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IntermediateAggregateResult {
+    pub by_category: HashMap<String, CategoryAccumulator>,
+}
+
+/// Final, human-facing aggregation result after [`IntermediateAggregateResult::finalize`].
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FinalAggregateResult {
+    pub category: String,
+    pub count: usize,
+    pub total_sales: f64,
+    pub avg_sales: f64,
+    pub min_sales: f64,
+    pub max_sales: f64,
+    pub variance: f64,
+}
+
+impl IntermediateAggregateResult {
+    fn from_rows(rows: &[DataRow]) -> Self {
+        let mut by_category: HashMap<String, CategoryAccumulator> = HashMap::new();
+        for row in rows {
+            by_category
+                .entry(row.category.clone())
+                .and_modify(|acc| acc.add(row.sales))
+                .or_insert_with(|| CategoryAccumulator::from_value(row.sales));
+        }
+        Self { by_category }
+    }
+
+    fn merge(&mut self, other: IntermediateAggregateResult) {
+        for (category, acc) in other.by_category {
+            self.by_category
+                .entry(category)
+                .and_modify(|existing| existing.merge(&acc))
+                .or_insert(acc);
+        }
+    }
+
+    fn finalize(self) -> Vec<FinalAggregateResult> {
+        self.by_category
+            .into_iter()
+            .map(|(category, acc)| FinalAggregateResult {
+                category,
+                count: acc.count,
+                total_sales: acc.sum,
+                avg_sales: acc.sum / acc.count as f64,
+                min_sales: acc.min,
+                max_sales: acc.max,
+                variance: acc.variance(),
+            })
+            .collect()
+    }
+}
+
+/// Combinable per-bucket count/sum state, keyed by a bucket identifier
+/// (a stringified bucket index for [`DataEngine::histogram`], or a range
+/// name for [`DataEngine::range_buckets`]). Shares the same merge shape as
+/// `CategoryAccumulator` so both bucketing styles can be fanned out across
+/// chunks and folded back together.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct BucketAccumulator {
+    pub count: usize,
+    pub total_sales: f64,
+}
+
+impl BucketAccumulator {
+    fn add(&mut self, sales: f64) {
+        self.count += 1;
+        self.total_sales += sales;
+    }
+
+    fn merge(&mut self, other: &BucketAccumulator) {
+        self.count += other.count;
+        self.total_sales += other.total_sales;
+    }
+}
+
+/// Combinable intermediate bucket state produced over one chunk of rows.
+/// Used by both [`DataEngine::histogram`] and [`DataEngine::range_buckets`];
+/// merge via [`DataEngine::merge_bucket_intermediates`], then finalize with
+/// the bucketing-specific `finalize_histogram`/`finalize_range_buckets`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IntermediateBucketResult {
+    pub buckets: HashMap<String, BucketAccumulator>,
+}
+
+impl IntermediateBucketResult {
+    fn merge(&mut self, other: IntermediateBucketResult) {
+        for (key, acc) in other.buckets {
+            self.buckets
+                .entry(key)
+                .and_modify(|existing| existing.merge(&acc))
+                .or_insert(acc);
+        }
+    }
+}
+
+/// One bucket of a [`DataEngine::histogram`] result.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HistogramBucket {
+    pub key: i64,
+    pub from: f64,
+    pub to: f64,
+    pub count: usize,
+    pub total_sales: f64,
+}
+
+/// A named `[from, to)` interval requested via [`DataEngine::range_buckets`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RangeSpec {
+    pub name: String,
+    pub from: f64,
+    pub to: f64,
+}
+
+/// One bucket of a [`DataEngine::range_buckets`] result. The overflow bucket
+/// (rows matching no range) has `from`/`to` of `None`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RangeBucketResult {
+    pub name: String,
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+    pub count: usize,
+    pub total_sales: f64,
+}
+
+const OVERFLOW_BUCKET: &str = "overflow";
+
+/// Fetch a required column from an Arrow batch and downcast it to the
+/// expected array type, producing a clear `JsValue` error otherwise.
+///
+/// Duplicated verbatim in `query-optimizer`'s `lib.rs` (see the note at the
+/// top of `spill.rs` for why, and when to stop copying).
+fn required_column<'a, T: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a T, JsValue> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| JsValue::from_str(&format!("Missing required column: {}", name)))?;
+
+    column
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| JsValue::from_str(&format!("Column '{}' has an unexpected type", name)))
+}
+
+/// Decode the `id`/`category`/`sales`/`region` columns of an Arrow batch into
+/// `DataRow`s, matching columns by name rather than position.
+fn rows_from_batch(batch: &RecordBatch) -> Result<Vec<DataRow>, JsValue> {
+    let id = required_column::<UInt32Array>(batch, "id")?;
+    let category = required_column::<StringArray>(batch, "category")?;
+    let sales = required_column::<Float64Array>(batch, "sales")?;
+    let region = required_column::<StringArray>(batch, "region")?;
+
+    Ok((0..batch.num_rows())
+        .map(|i| DataRow {
+            id: id.value(i),
+            category: category.value(i).to_string(),
+            sales: sales.value(i),
+            region: region.value(i).to_string(),
+        })
+        .collect())
+}
+
+fn histogram_buckets(
+    rows: &[DataRow],
+    field: &str,
+    bucket_width: f64,
+    offset: f64,
+) -> Result<HashMap<String, BucketAccumulator>, JsValue> {
+    if field != "sales" {
+        return Err(JsValue::from_str(&format!(
+            "Unknown numeric field for histogram: {}",
+            field
+        )));
+    }
+    if bucket_width <= 0.0 {
+        return Err(JsValue::from_str("bucket_width must be positive"));
+    }
+
+    let mut buckets: HashMap<String, BucketAccumulator> = HashMap::new();
+    for row in rows {
+        let index = ((row.sales - offset) / bucket_width).floor() as i64;
+        buckets.entry(index.to_string()).or_default().add(row.sales);
+    }
+    Ok(buckets)
+}
+
+fn finalize_histogram(
+    buckets: HashMap<String, BucketAccumulator>,
+    bucket_width: f64,
+    offset: f64,
+) -> Vec<HistogramBucket> {
+    let mut results: Vec<HistogramBucket> = buckets
+        .into_iter()
+        .map(|(key, acc)| {
+            let key: i64 = key.parse().unwrap_or(0);
+            let from = offset + key as f64 * bucket_width;
+            HistogramBucket {
+                key,
+                from,
+                to: from + bucket_width,
+                count: acc.count,
+                total_sales: acc.total_sales,
+            }
+        })
+        .collect();
+    results.sort_by_key(|b| b.key);
+    results
+}
+
+fn range_bucket_accumulate(
+    rows: &[DataRow],
+    specs: &[RangeSpec],
+) -> HashMap<String, BucketAccumulator> {
+    let mut buckets: HashMap<String, BucketAccumulator> = HashMap::new();
+    for row in rows {
+        let matched = specs
+            .iter()
+            .find(|spec| row.sales >= spec.from && row.sales < spec.to);
+
+        let key = match matched {
+            Some(spec) => spec.name.clone(),
+            None => OVERFLOW_BUCKET.to_string(),
+        };
+        buckets.entry(key).or_default().add(row.sales);
+    }
+    buckets
+}
+
+fn finalize_range_buckets(
+    mut buckets: HashMap<String, BucketAccumulator>,
+    specs: &[RangeSpec],
+) -> Vec<RangeBucketResult> {
+    let mut results: Vec<RangeBucketResult> = specs
+        .iter()
+        .map(|spec| {
+            let acc = buckets.remove(&spec.name).unwrap_or_default();
+            RangeBucketResult {
+                name: spec.name.clone(),
+                from: Some(spec.from),
+                to: Some(spec.to),
+                count: acc.count,
+                total_sales: acc.total_sales,
+            }
+        })
+        .collect();
+
+    let overflow = buckets.remove(OVERFLOW_BUCKET).unwrap_or_default();
+    results.push(RangeBucketResult {
+        name: OVERFLOW_BUCKET.to_string(),
+        from: None,
+        to: None,
+        count: overflow.count,
+        total_sales: overflow.total_sales,
+    });
+
+    results
+}
+
 /// Main data engine for processing tabular data
 #[wasm_bindgen]
 pub struct DataEngine {
     data: Vec<DataRow>,
+    spill: SpillManager,
+    external_store: Option<JsValue>,
 }
 
 #[wasm_bindgen]
@@ -43,7 +393,97 @@ impl DataEngine {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         init_panic_hook();
-        Self { data: Vec::new() }
+        Self {
+            data: Vec::new(),
+            spill: SpillManager::default(),
+            external_store: None,
+        }
+    }
+
+    /// Set the resident byte budget for external (spill) mode. Once
+    /// exceeded, `load_data_streaming` hands its oldest resident partitions
+    /// off to the store registered via `set_external_store` instead of
+    /// keeping every streamed partition in memory. Has no effect on rows
+    /// already loaded via `load_data`.
+    pub fn set_spill_budget(&mut self, bytes: usize) {
+        self.spill.set_budget(bytes);
+    }
+
+    /// Register the host-provided async store (e.g. an IndexedDB or OPFS
+    /// wrapper) that spilled partitions are written to and read back from.
+    /// Must expose async `put(key, rows) -> Promise` and
+    /// `get(key) -> Promise<rows>` methods.
+    pub fn set_external_store(&mut self, store: JsValue) {
+        self.external_store = Some(store);
+    }
+
+    /// Incrementally ingest rows from a host-provided async `reader`
+    /// exposing a `next() -> Promise<{done, value}>` method (mirroring a
+    /// `ReadableStreamDefaultReader`), where each `value` is a JSON array of
+    /// rows. Each batch is split into fixed-size partitions held apart from
+    /// `data`; whenever the resident partitions exceed the spill budget, the
+    /// oldest resident one is written to the registered external store, so
+    /// the whole dataset never needs to be resident at once. Requires
+    /// `set_external_store` to have been called first.
+    pub async fn load_data_streaming(&mut self, reader: JsValue) -> Result<(), JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before load_data_streaming"))?;
+
+        let next_fn: js_sys::Function = js_sys::Reflect::get(&reader, &"next".into())?.dyn_into()?;
+
+        loop {
+            let promise: js_sys::Promise = next_fn.call0(&reader)?.dyn_into()?;
+            let result = JsFuture::from(promise).await?;
+
+            let done = js_sys::Reflect::get(&result, &"done".into())?
+                .as_bool()
+                .unwrap_or(false);
+            if done {
+                break;
+            }
+
+            let value = js_sys::Reflect::get(&result, &"value".into())?;
+            let batch: Vec<DataRow> = serde_wasm_bindgen::from_value(value)
+                .map_err(|e| JsValue::from_str(&format!("Failed to parse streamed batch: {}", e)))?;
+
+            self.spill.ingest(&batch, &store).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Total row count across both `data` and any partitions ingested via
+    /// `load_data_streaming` (resident or spilled).
+    pub fn row_count_external(&self) -> usize {
+        self.data.len() + self.spill.row_count()
+    }
+
+    /// Aggregate by category across both resident `data` (loaded via
+    /// `load_data`/`load_arrow_ipc`/`load_parquet`) and every partition
+    /// ingested via `load_data_streaming`, fetching spilled partitions from
+    /// the external store one at a time and folding everything with the
+    /// same mergeable intermediate-result machinery used for Web Worker
+    /// partials, so at most one spilled partition is fetched back into
+    /// memory at a time. Matches `row_count_external`, which also
+    /// counts both sources.
+    pub async fn aggregate_by_category_external(&self) -> Result<JsValue, JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before aggregating externally"))?;
+
+        let mut combined = IntermediateAggregateResult::from_rows(&self.data);
+        self.spill
+            .for_each_partition(&store, |rows| {
+                combined.merge(IntermediateAggregateResult::from_rows(rows));
+            })
+            .await?;
+
+        let results = combined.finalize();
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
     /// Load data from JSON string
@@ -53,6 +493,56 @@ impl DataEngine {
         Ok(())
     }
 
+    /// Load data from an Arrow IPC stream, mapping columns by name and
+    /// avoiding the JSON parse/allocate cost of `load_data`.
+    pub fn load_arrow_ipc(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let reader = StreamReader::try_new(Cursor::new(bytes), None)
+            .map_err(|e| JsValue::from_str(&format!("Failed to read Arrow IPC stream: {}", e)))?;
+
+        let mut rows = Vec::new();
+        for batch in reader {
+            let batch = batch
+                .map_err(|e| JsValue::from_str(&format!("Failed to decode Arrow batch: {}", e)))?;
+            rows.extend(rows_from_batch(&batch)?);
+        }
+
+        self.data = rows;
+        Ok(())
+    }
+
+    /// Load data from a Parquet file's bytes, mapping columns by name. Row
+    /// groups are read one at a time via a fresh reader scoped to just that
+    /// group (`Bytes` clones are cheap refcounted views, not copies), which
+    /// keeps the decoded Arrow batches bounded by a single row group and sets
+    /// up a future streaming load mode. The input bytes are copied once, and
+    /// the rows of every row group are collected into `data`.
+    pub fn load_parquet(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let bytes = bytes::Bytes::copy_from_slice(bytes);
+        let num_row_groups = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to open Parquet file: {}", e)))?
+            .metadata()
+            .num_row_groups();
+
+        let mut rows = Vec::new();
+        for row_group in 0..num_row_groups {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+                .map_err(|e| JsValue::from_str(&format!("Failed to open Parquet file: {}", e)))?
+                .with_row_groups(vec![row_group])
+                .build()
+                .map_err(|e| JsValue::from_str(&format!("Failed to build Parquet reader: {}", e)))?;
+
+            for batch in reader {
+                let batch = batch.map_err(|e| {
+                    JsValue::from_str(&format!("Failed to decode Parquet row group: {}", e))
+                })?;
+                rows.extend(rows_from_batch(&batch)?);
+            }
+        }
+
+        self.data = rows;
+        Ok(())
+    }
+
     /// Get the number of rows loaded
     pub fn row_count(&self) -> usize {
         self.data.len()
@@ -82,6 +572,119 @@ impl DataEngine {
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
+    /// Compute a combinable intermediate aggregation over the currently
+    /// loaded rows (e.g. one chunk owned by a single worker). Pass the
+    /// result to [`merge_intermediates`] alongside other chunks' partials to
+    /// get a final answer without re-scanning any row twice.
+    pub fn aggregate_by_category_intermediate(&self) -> Result<JsValue, JsValue> {
+        let intermediate = IntermediateAggregateResult::from_rows(&self.data);
+        serde_wasm_bindgen::to_value(&intermediate)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Merge many `IntermediateAggregateResult`s (as produced by
+    /// `aggregate_by_category_intermediate`) into a single finalized result.
+    /// `parts` must be a JS array of such intermediates.
+    pub fn merge_intermediates(parts: JsValue) -> Result<JsValue, JsValue> {
+        let parts: Vec<IntermediateAggregateResult> = serde_wasm_bindgen::from_value(parts)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse intermediates: {}", e)))?;
+
+        let mut combined = IntermediateAggregateResult::default();
+        for part in parts {
+            combined.merge(part);
+        }
+
+        let results = combined.finalize();
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Bucket rows into fixed-width intervals over a numeric field (only
+    /// `"sales"` is currently supported). Bucket `i` covers the half-open
+    /// interval `[offset + i*bucket_width, offset + (i+1)*bucket_width)`.
+    /// Returns buckets ordered by key ascending.
+    pub fn histogram(&self, field: &str, bucket_width: f64, offset: f64) -> Result<JsValue, JsValue> {
+        let buckets = histogram_buckets(&self.data, field, bucket_width, offset)?;
+        let results = finalize_histogram(buckets, bucket_width, offset);
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Combinable intermediate histogram state for the currently loaded
+    /// rows. Merge partials with `merge_bucket_intermediates`, then finalize
+    /// with `finalize_histogram` using the same `bucket_width`/`offset`.
+    pub fn histogram_intermediate(
+        &self,
+        field: &str,
+        bucket_width: f64,
+        offset: f64,
+    ) -> Result<JsValue, JsValue> {
+        let buckets = histogram_buckets(&self.data, field, bucket_width, offset)?;
+        serde_wasm_bindgen::to_value(&IntermediateBucketResult { buckets })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Finalize a merged histogram intermediate (from
+    /// `merge_bucket_intermediates`) into ordered, human-facing buckets.
+    pub fn finalize_histogram(merged: JsValue, bucket_width: f64, offset: f64) -> Result<JsValue, JsValue> {
+        let merged: IntermediateBucketResult = serde_wasm_bindgen::from_value(merged)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse intermediate: {}", e)))?;
+        let results = finalize_histogram(merged.buckets, bucket_width, offset);
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Bucket rows by `sales` into named, half-open `[from, to)` ranges.
+    /// `ranges` is a JSON array of `{name, from, to}`. Rows matching no
+    /// range are counted in a final `"overflow"` bucket.
+    pub fn range_buckets(&self, ranges: &str) -> Result<JsValue, JsValue> {
+        let specs: Vec<RangeSpec> = serde_json::from_str(ranges)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse ranges: {}", e)))?;
+        let buckets = range_bucket_accumulate(&self.data, &specs);
+        let results = finalize_range_buckets(buckets, &specs);
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Combinable intermediate range-bucket state for the currently loaded
+    /// rows. Merge partials with `merge_bucket_intermediates`, then finalize
+    /// with `finalize_range_buckets` using the same `ranges`.
+    pub fn range_buckets_intermediate(&self, ranges: &str) -> Result<JsValue, JsValue> {
+        let specs: Vec<RangeSpec> = serde_json::from_str(ranges)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse ranges: {}", e)))?;
+        let buckets = range_bucket_accumulate(&self.data, &specs);
+        serde_wasm_bindgen::to_value(&IntermediateBucketResult { buckets })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Finalize a merged range-bucket intermediate (from
+    /// `merge_bucket_intermediates`) into named buckets plus overflow.
+    pub fn finalize_range_buckets(merged: JsValue, ranges: &str) -> Result<JsValue, JsValue> {
+        let merged: IntermediateBucketResult = serde_wasm_bindgen::from_value(merged)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse intermediate: {}", e)))?;
+        let specs: Vec<RangeSpec> = serde_json::from_str(ranges)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse ranges: {}", e)))?;
+        let results = finalize_range_buckets(merged.buckets, &specs);
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Merge many `IntermediateBucketResult`s (from either
+    /// `histogram_intermediate` or `range_buckets_intermediate`) into one.
+    /// `parts` must be a JS array of such intermediates.
+    pub fn merge_bucket_intermediates(parts: JsValue) -> Result<JsValue, JsValue> {
+        let parts: Vec<IntermediateBucketResult> = serde_wasm_bindgen::from_value(parts)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse intermediates: {}", e)))?;
+
+        let mut combined = IntermediateBucketResult::default();
+        for part in parts {
+            combined.merge(part);
+        }
+
+        serde_wasm_bindgen::to_value(&combined)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
     /// Filter data by minimum sales value
     pub fn filter_by_sales(&self, min_sales: f64) -> Result<JsValue, JsValue> {
         let filtered: Vec<&DataRow> = self.data
@@ -93,6 +696,33 @@ impl DataEngine {
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
+    /// Filter by minimum sales value across both resident `data` and every
+    /// partition ingested via `load_data_streaming`, fetching spilled
+    /// partitions from the external store one at a time instead of
+    /// requiring the whole dataset resident. Matches `row_count_external`,
+    /// which also counts both sources.
+    pub async fn filter_by_sales_external(&self, min_sales: f64) -> Result<JsValue, JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before filtering externally"))?;
+
+        let mut matched: Vec<DataRow> = self
+            .data
+            .iter()
+            .filter(|row| row.sales >= min_sales)
+            .cloned()
+            .collect();
+        self.spill
+            .for_each_partition(&store, |rows| {
+                matched.extend(rows.iter().filter(|row| row.sales >= min_sales).cloned());
+            })
+            .await?;
+
+        serde_wasm_bindgen::to_value(&matched)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
     /// Pivot data: create a region x category matrix
     pub fn pivot_region_category(&self) -> Result<JsValue, JsValue> {
         let mut pivot: HashMap<String, HashMap<String, f64>> = HashMap::new();
@@ -108,8 +738,9 @@ impl DataEngine {
 }
 
 // Extension points for advanced features:
-// 1. Implement Arrow format support for zero-copy data transfer
-// 2. Add streaming API for processing large datasets in chunks
-// 3. Implement custom binary serialization for better performance
-// 4. Add support for more complex aggregations (percentiles, variance, etc.)
-// 5. Implement indexing for faster filtering operations
+// 1. Implement custom binary serialization for better performance
+// 2. Add support for more complex aggregations (percentiles, etc.)
+// 3. Implement indexing for faster filtering operations
+// 4. Add an external (partition-and-merge) sort/group-by fallback for
+//    `histogram`/`range_buckets`/pivot when group cardinality or sort input
+//    overflows the spill budget (currently only aggregate/filter stream)
@@ -0,0 +1,158 @@
+//! External-storage ("spill") mode for [`crate::DataEngine`].
+//!
+//! Rows are appended in fixed-size partitions. Once the resident byte
+//! budget configured via `DataEngine::set_spill_budget` is exceeded, the
+//! oldest resident partition is handed off to a host-provided async store
+//! (IndexedDB or OPFS) and dropped from linear memory. Aggregation streams
+//! partitions back in one at a time, combining them with the same
+//! mergeable intermediate-result machinery used for Web Worker partials, so
+//! only a bounded working set is ever resident.
+//!
+//! `query-optimizer`'s `spill` module mirrors this one near-identically, and
+//! `DataRow`/`required_column`/`rows_from_batch` are duplicated the same
+//! way across both crates' `lib.rs`. Each `rust-modules/*` crate is built
+//! and versioned independently with no shared workspace member today, so
+//! there's nowhere to put a common crate without first introducing one;
+//! if a third crate ends up needing this logic, that's the point to pull
+//! `DataRow`, the Arrow/Parquet ingestion helpers, and `spill` out into a
+//! shared `rust-modules/common` crate instead of copying again.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::DataRow;
+
+/// Rows per partition, spilled or resident.
+const PARTITION_SIZE: usize = 4096;
+/// Rough per-row footprint (the `id`/`sales` fields plus `category`/`region`
+/// string overhead) used to decide when the resident set has grown past
+/// budget. Not exact, but good enough to trigger a spill before real memory
+/// pressure hits.
+const APPROX_BYTES_PER_ROW: usize = 96;
+
+enum Partition {
+    Resident(Vec<DataRow>),
+    Spilled { key: JsValue, len: usize },
+}
+
+/// Tracks an engine's resident/spilled row partitions and the byte budget
+/// controlling when new partitions spill.
+pub struct SpillManager {
+    budget_bytes: usize,
+    partitions: Vec<Partition>,
+}
+
+impl Default for SpillManager {
+    fn default() -> Self {
+        // No budget configured yet means never spill.
+        Self {
+            budget_bytes: usize::MAX,
+            partitions: Vec::new(),
+        }
+    }
+}
+
+impl SpillManager {
+    pub fn set_budget(&mut self, bytes: usize) {
+        self.budget_bytes = bytes;
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.partitions
+            .iter()
+            .map(|p| match p {
+                Partition::Resident(rows) => rows.len(),
+                Partition::Spilled { len, .. } => *len,
+            })
+            .sum()
+    }
+
+    fn resident_bytes(&self) -> usize {
+        self.partitions
+            .iter()
+            .map(|p| match p {
+                Partition::Resident(rows) => rows.len() * APPROX_BYTES_PER_ROW,
+                Partition::Spilled { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// Append `rows`, splitting into fixed-size partitions, spilling the
+    /// oldest resident partition to `store` whenever the budget is
+    /// exceeded. `store` must provide an async `put(key, rows) -> Promise`.
+    pub async fn ingest(&mut self, rows: &[DataRow], store: &JsValue) -> Result<(), JsValue> {
+        for chunk in rows.chunks(PARTITION_SIZE) {
+            self.partitions.push(Partition::Resident(chunk.to_vec()));
+            while self.resident_bytes() > self.budget_bytes {
+                if !self.spill_oldest_resident(store).await? {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Spill the oldest still-resident partition. Returns `false` if there
+    /// was nothing left to spill.
+    async fn spill_oldest_resident(&mut self, store: &JsValue) -> Result<bool, JsValue> {
+        let Some(index) = self
+            .partitions
+            .iter()
+            .position(|p| matches!(p, Partition::Resident(_)))
+        else {
+            return Ok(false);
+        };
+
+        let Partition::Resident(rows) = std::mem::replace(
+            &mut self.partitions[index],
+            Partition::Spilled {
+                key: JsValue::NULL,
+                len: 0,
+            },
+        ) else {
+            unreachable!("position() only matches Resident partitions");
+        };
+
+        let key = JsValue::from_str(&format!("partition-{}", index));
+        let payload = serde_wasm_bindgen::to_value(&rows)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
+
+        let put_fn: js_sys::Function = js_sys::Reflect::get(store, &"put".into())?.dyn_into()?;
+        let promise: js_sys::Promise = put_fn.call2(store, &key, &payload)?.dyn_into()?;
+        JsFuture::from(promise).await?;
+
+        self.partitions[index] = Partition::Spilled {
+            key,
+            len: rows.len(),
+        };
+        Ok(true)
+    }
+
+    /// Stream every partition back in order, calling `visit` with each
+    /// batch of rows. Spilled partitions are fetched one at a time from
+    /// `store` via an async `get(key) -> Promise<rows>`, so at most one
+    /// spilled partition is held in memory at a time during the scan.
+    pub async fn for_each_partition(
+        &self,
+        store: &JsValue,
+        mut visit: impl FnMut(&[DataRow]),
+    ) -> Result<(), JsValue> {
+        for partition in &self.partitions {
+            match partition {
+                Partition::Resident(rows) => visit(rows),
+                Partition::Spilled { key, .. } => {
+                    let get_fn: js_sys::Function =
+                        js_sys::Reflect::get(store, &"get".into())?.dyn_into()?;
+                    let promise: js_sys::Promise = get_fn.call1(store, key)?.dyn_into()?;
+                    let value = JsFuture::from(promise).await?;
+                    let rows: Vec<DataRow> = serde_wasm_bindgen::from_value(value).map_err(|e| {
+                        JsValue::from_str(&format!("Failed to parse spilled partition: {}", e))
+                    })?;
+                    visit(&rows);
+                }
+            }
+        }
+        Ok(())
+    }
+}
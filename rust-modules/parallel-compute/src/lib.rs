@@ -1,5 +1,6 @@
 use wasm_bindgen::prelude::*;
 use js_sys::Float64Array;
+use serde::{Deserialize, Serialize};
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
@@ -7,6 +8,82 @@ pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+/// At or below this slice length, a range is reduced directly instead of being
+/// split further. Tuned so leaves do enough work to amortize the overhead
+/// of a rayon task (when the `threads` feature is enabled) or a function
+/// call (when it isn't).
+const SPLIT_THRESHOLD: usize = 4096;
+
+/// Which reduction `process_parallel` should run over the data.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReducerKind {
+    SumOfSquares,
+    Sum,
+    Min,
+    Max,
+    Count,
+}
+
+fn leaf_reduce(data: &[f64], reducer: ReducerKind) -> f64 {
+    match reducer {
+        ReducerKind::SumOfSquares => data.iter().map(|&x| x * x).sum(),
+        ReducerKind::Sum => data.iter().sum(),
+        ReducerKind::Min => data.iter().cloned().fold(f64::INFINITY, f64::min),
+        ReducerKind::Max => data.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        ReducerKind::Count => data.len() as f64,
+    }
+}
+
+/// Associative combine operation for two child results. Shared by every
+/// reducer kind so the splitter below only needs to know how to traverse,
+/// not how to reduce.
+fn combine(reducer: ReducerKind, left: f64, right: f64) -> f64 {
+    match reducer {
+        ReducerKind::SumOfSquares | ReducerKind::Sum | ReducerKind::Count => left + right,
+        ReducerKind::Min => left.min(right),
+        ReducerKind::Max => left.max(right),
+    }
+}
+
+/// Recursively split `data` in half until a slice is no longer than
+/// `SPLIT_THRESHOLD`, reduce each leaf, then combine results bottom-up.
+/// Generic over any associative `combine`, so sum/min/max/count all reuse
+/// this one traversal instead of separate ad-hoc chunking methods.
+///
+/// With the `threads` feature enabled, the two halves are handed to
+/// `rayon::join`, which runs them on the `wasm-bindgen-rayon` worker pool
+/// and steals work across threads when one half finishes early; without it,
+/// the same traversal runs sequentially on the calling thread.
+fn split_reduce(data: &[f64], reducer: ReducerKind) -> f64 {
+    if data.len() <= SPLIT_THRESHOLD {
+        return leaf_reduce(data, reducer);
+    }
+
+    let mid = data.len() / 2;
+    let (left, right) = data.split_at(mid);
+
+    #[cfg(feature = "threads")]
+    let (left_result, right_result) = rayon::join(
+        || split_reduce(left, reducer),
+        || split_reduce(right, reducer),
+    );
+
+    #[cfg(not(feature = "threads"))]
+    let (left_result, right_result) = (split_reduce(left, reducer), split_reduce(right, reducer));
+
+    combine(reducer, left_result, right_result)
+}
+
+/// Initialize the `wasm-bindgen-rayon` worker pool with `num_threads`
+/// workers. Only available when built with the `threads` feature; callers
+/// must `await` the returned promise before calling `process_parallel`.
+#[cfg(feature = "threads")]
+#[wasm_bindgen]
+pub fn init_thread_pool(num_threads: usize) -> js_sys::Promise {
+    wasm_bindgen_rayon::init_thread_pool(num_threads)
+}
+
 /// Parallel processor for multi-threaded computations
 #[wasm_bindgen]
 pub struct ParallelProcessor {
@@ -50,6 +127,14 @@ impl ParallelProcessor {
         self.data.iter().map(|&x| x * x).sum()
     }
 
+    /// Process the entire dataset with a balanced, work-stealing divide-and-conquer
+    /// reduction instead of manually chunking. The same splitter runs either
+    /// sequentially or across the `wasm-bindgen-rayon` worker pool depending on
+    /// whether the crate was built with the `threads` feature.
+    pub fn process_parallel(&self, reducer_kind: ReducerKind) -> f64 {
+        split_reduce(&self.data, reducer_kind)
+    }
+
     /// Get a chunk of data as Float64Array for transfer to Web Worker
     pub fn get_chunk_array(&self, start: usize, end: usize) -> Float64Array {
         let end = end.min(self.data.len());
@@ -102,9 +187,125 @@ impl ParallelProcessor {
         js_sys::Reflect::set(&result, &"min".into(), &min.into()).unwrap();
         js_sys::Reflect::set(&result, &"max".into(), &max.into()).unwrap();
         js_sys::Reflect::set(&result, &"count".into(), &count.into()).unwrap();
-        
+
         result.into()
     }
+
+    /// Compute a combinable intermediate stats accumulator for a chunk,
+    /// suitable for computing in a Web Worker and folding together later via
+    /// [`merge_stat_intermediates`] without rescanning the data.
+    pub fn calculate_stats_intermediate(&self, start: usize, end: usize) -> JsValue {
+        let end = end.min(self.data.len());
+        if start >= end {
+            return serde_wasm_bindgen::to_value(&StatsAccumulator::default()).unwrap();
+        }
+
+        let mut acc = StatsAccumulator::default();
+        for &value in &self.data[start..end] {
+            acc.add(value);
+        }
+
+        serde_wasm_bindgen::to_value(&acc).unwrap()
+    }
+
+    /// Merge many `StatsAccumulator`s (as produced by
+    /// `calculate_stats_intermediate`) into a single finalized stats object.
+    /// `parts` must be a JS array of such intermediates.
+    pub fn merge_stat_intermediates(parts: JsValue) -> Result<JsValue, JsValue> {
+        let parts: Vec<StatsAccumulator> = serde_wasm_bindgen::from_value(parts)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse intermediates: {}", e)))?;
+
+        let mut combined = StatsAccumulator::default();
+        for part in parts {
+            combined.merge(&part);
+        }
+
+        let result = js_sys::Object::new();
+        js_sys::Reflect::set(&result, &"sum".into(), &combined.sum.into()).unwrap();
+        js_sys::Reflect::set(&result, &"mean".into(), &combined.mean.into()).unwrap();
+        js_sys::Reflect::set(&result, &"min".into(), &combined.min.into()).unwrap();
+        js_sys::Reflect::set(&result, &"max".into(), &combined.max.into()).unwrap();
+        js_sys::Reflect::set(&result, &"count".into(), &(combined.count as f64).into()).unwrap();
+        js_sys::Reflect::set(
+            &result,
+            &"variance".into(),
+            &combined.variance().into(),
+        )
+        .unwrap();
+
+        Ok(result.into())
+    }
+}
+
+/// Combinable stats state for a chunk of `f64`s. Variance uses Welford's
+/// online algorithm so independently-computed chunks merge via the parallel
+/// formula without rescanning the underlying data.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatsAccumulator {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub m2: f64,
+}
+
+impl Default for StatsAccumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl StatsAccumulator {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        let delta2 = value - self.mean;
+        self.m2 += delta * delta2;
+    }
+
+    /// Merge another accumulator's state into this one using the parallel
+    /// variance formula.
+    fn merge(&mut self, other: &StatsAccumulator) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        let n_a = self.count as f64;
+        let n_b = other.count as f64;
+        let n = n_a + n_b;
+        let delta = other.mean - self.mean;
+
+        self.mean += delta * n_b / n;
+        self.m2 += other.m2 + delta * delta * n_a * n_b / n;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+    }
+
+    fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
 }
 
 /// Standalone function for Web Worker to call
@@ -122,28 +323,28 @@ pub fn process_worker_chunk(data: Float64Array) -> f64 {
 }
 
 // Extension points:
-// 1. Enable rayon for true parallel processing with WASM threads
-// 2. Implement work-stealing scheduler for better load balancing
-// 3. Add support for SharedArrayBuffer with Atomics
-// 4. Implement thread pool management
-// 5. Add memory-mapped file support for very large datasets
-// 6. Implement async/await patterns for better composability
+// 1. Add support for SharedArrayBuffer with Atomics
+// 2. Add memory-mapped file support for very large datasets
+// 3. Implement async/await patterns for better composability
+// 4. Add a spill-to-IndexedDB external mode (see `data-engine`'s `spill`
+//    module) once `ParallelProcessor` loads real series instead of
+//    generating sample data in its constructor
 
 /*
-To enable WASM threads (requires additional setup):
+To build with real WASM threads (requires additional setup):
 
 1. Add to Cargo.toml:
-   rayon = "1.8"
-   wasm-bindgen-rayon = "1.0"
+   [features]
+   threads = ["dep:rayon", "dep:wasm-bindgen-rayon"]
+
+   [dependencies]
+   rayon = { version = "1.8", optional = true }
+   wasm-bindgen-rayon = { version = "1.0", optional = true }
 
 2. Build with threads:
    RUSTFLAGS='-C target-feature=+atomics,+bulk-memory,+mutable-globals' \
-   cargo build --target wasm32-unknown-unknown -Z build-std=std,panic_abort
-
-3. Use rayon for parallel processing:
-   use rayon::prelude::*;
-   
-   pub fn process_parallel(&self) -> f64 {
-       self.data.par_iter().map(|&x| x * x).sum()
-   }
+   cargo build --target wasm32-unknown-unknown -Z build-std=std,panic_abort --features threads
+
+3. From JS, await `init_thread_pool(navigator.hardwareConcurrency)` once
+   before calling `process_parallel`.
 */
@@ -1,6 +1,19 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::io::Cursor;
+
+use arrow::array::{Array, Float64Array, StringArray, UInt32Array};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+mod plan;
+mod spill;
+
+use plan::{optimize, parse_plan};
+use spill::SpillManager;
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
@@ -17,14 +30,6 @@ pub struct DataRow {
     pub region: String,
 }
 
-/// Query execution plan step
-#[derive(Serialize, Deserialize, Debug)]
-pub struct QueryPlanStep {
-    pub operation: String,
-    pub cost: f64,
-    pub rows_estimated: usize,
-}
-
 /// Query execution result
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryResult {
@@ -33,10 +38,65 @@ pub struct QueryResult {
     pub rows_scanned: usize,
 }
 
+/// Fetch a required column from an Arrow batch and downcast it to the
+/// expected array type, producing a clear `JsValue` error otherwise.
+///
+/// Duplicated verbatim in `data-engine`'s `lib.rs` (see the note at the top
+/// of `spill.rs` for why, and when to stop copying).
+fn required_column<'a, T: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a T, JsValue> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| JsValue::from_str(&format!("Missing required column: {}", name)))?;
+
+    column
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| JsValue::from_str(&format!("Column '{}' has an unexpected type", name)))
+}
+
+/// Decode the `id`/`category`/`sales`/`region` columns of an Arrow batch into
+/// `DataRow`s, matching columns by name rather than position.
+fn rows_from_batch(batch: &RecordBatch) -> Result<Vec<DataRow>, JsValue> {
+    let id = required_column::<UInt32Array>(batch, "id")?;
+    let category = required_column::<StringArray>(batch, "category")?;
+    let sales = required_column::<Float64Array>(batch, "sales")?;
+    let region = required_column::<StringArray>(batch, "region")?;
+
+    Ok((0..batch.num_rows())
+        .map(|i| DataRow {
+            id: id.value(i),
+            category: category.value(i).to_string(),
+            sales: sales.value(i),
+            region: region.value(i).to_string(),
+        })
+        .collect())
+}
+
+/// Resolve a `GROUP BY` column name to a key-extraction function over
+/// `DataRow`, for `QueryEngine::group_by_external`. Only the two text
+/// columns are valid group keys, matching what `plan::aggregate_rows`
+/// effectively supports (it reads the group key as a string, so a numeric
+/// column there would put every row into one group with an empty key).
+fn group_by_key_fn(group_by: &str) -> Result<fn(&DataRow) -> String, JsValue> {
+    match group_by {
+        "category" => Ok(|row: &DataRow| row.category.clone()),
+        "region" => Ok(|row: &DataRow| row.region.clone()),
+        other => Err(JsValue::from_str(&format!(
+            "Unsupported GROUP BY column for external aggregation: {}",
+            other
+        ))),
+    }
+}
+
 /// Simple SQL-like query engine
 #[wasm_bindgen]
 pub struct QueryEngine {
     data: Vec<DataRow>,
+    spill: SpillManager,
+    external_store: Option<JsValue>,
 }
 
 #[wasm_bindgen]
@@ -45,7 +105,163 @@ impl QueryEngine {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         init_panic_hook();
-        Self { data: Vec::new() }
+        Self {
+            data: Vec::new(),
+            spill: SpillManager::default(),
+            external_store: None,
+        }
+    }
+
+    /// Set the resident byte budget for external (spill) mode. Once
+    /// exceeded, `load_data_streaming` hands partitions off to the store
+    /// registered via `set_external_store` instead of keeping every
+    /// streamed partition in memory.
+    pub fn set_spill_budget(&mut self, bytes: usize) {
+        self.spill.set_budget(bytes);
+    }
+
+    /// Register the host-provided async store (e.g. an IndexedDB or OPFS
+    /// wrapper) that spilled partitions are written to and read back from.
+    /// Must expose async `put(key, rows) -> Promise` and
+    /// `get(key) -> Promise<rows>` methods.
+    pub fn set_external_store(&mut self, store: JsValue) {
+        self.external_store = Some(store);
+    }
+
+    /// Incrementally ingest rows from a host-provided async `reader`
+    /// exposing a `next() -> Promise<{done, value}>` method, where each
+    /// `value` is a JSON array of rows. Mirrors
+    /// `DataEngine::load_data_streaming`; requires `set_external_store` to
+    /// have been called first. Streamed partitions are read by
+    /// `scan_external`, `filter_by_sales_external` and `group_by_external`;
+    /// `execute_query` and `explain_query` read only `data`, and there is no
+    /// external sort — see the extension points below.
+    pub async fn load_data_streaming(&mut self, reader: JsValue) -> Result<(), JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before load_data_streaming"))?;
+
+        let next_fn: js_sys::Function = js_sys::Reflect::get(&reader, &"next".into())?.dyn_into()?;
+
+        loop {
+            let promise: js_sys::Promise = next_fn.call0(&reader)?.dyn_into()?;
+            let result = JsFuture::from(promise).await?;
+
+            let done = js_sys::Reflect::get(&result, &"done".into())?
+                .as_bool()
+                .unwrap_or(false);
+            if done {
+                break;
+            }
+
+            let value = js_sys::Reflect::get(&result, &"value".into())?;
+            let batch: Vec<DataRow> = serde_wasm_bindgen::from_value(value)
+                .map_err(|e| JsValue::from_str(&format!("Failed to parse streamed batch: {}", e)))?;
+
+            self.spill.ingest(&batch, &store).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Total row count across both `data` and any partitions ingested via
+    /// `load_data_streaming` (resident or spilled).
+    pub fn row_count_external(&self) -> usize {
+        self.data.len() + self.spill.row_count()
+    }
+
+    /// Scan every partition ingested via `load_data_streaming` back into a
+    /// single JSON array, fetching spilled partitions from the external
+    /// store one at a time. Reassembles the whole table in memory, so it's
+    /// only appropriate for a final result the caller already expects to be
+    /// small; for a true out-of-core scan see `filter_by_sales_external`.
+    /// Full plan execution (filter/aggregate/sort) over spilled partitions
+    /// is not yet wired up.
+    pub async fn scan_external(&self) -> Result<JsValue, JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before scanning externally"))?;
+
+        let mut rows: Vec<DataRow> = Vec::new();
+        self.spill
+            .for_each_partition(&store, |partition| rows.extend_from_slice(partition))
+            .await?;
+
+        serde_wasm_bindgen::to_value(&rows)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Filter by minimum sales value across both resident `data` and every
+    /// partition ingested via `load_data_streaming`, fetching spilled
+    /// partitions from the external store one at a time instead of
+    /// reassembling the whole table like `scan_external` does. Matches
+    /// `row_count_external`, which also counts both sources. Mirrors
+    /// `DataEngine::filter_by_sales_external`.
+    pub async fn filter_by_sales_external(&self, min_sales: f64) -> Result<JsValue, JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before filtering externally"))?;
+
+        let mut matched: Vec<DataRow> = self
+            .data
+            .iter()
+            .filter(|row| row.sales >= min_sales)
+            .cloned()
+            .collect();
+        self.spill
+            .for_each_partition(&store, |rows| {
+                matched.extend(rows.iter().filter(|row| row.sales >= min_sales).cloned());
+            })
+            .await?;
+
+        serde_wasm_bindgen::to_value(&matched)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Group by `category` or `region` and sum/count/average `sales` across
+    /// both resident `data` and every partition ingested via
+    /// `load_data_streaming`, fetching spilled partitions from the external
+    /// store one at a time — the external fallback for a `GROUP BY` whose
+    /// source rows overflow the spill budget. At most one spilled partition
+    /// is fetched at a time, unlike `execute_query`, which requires
+    /// `self.data` to already fit in memory. The running per-group totals
+    /// are all kept in one in-memory map, so the number of groups is not
+    /// bounded by the spill budget.
+    pub async fn group_by_external(&self, group_by: &str) -> Result<JsValue, JsValue> {
+        let store = self
+            .external_store
+            .clone()
+            .ok_or_else(|| JsValue::from_str("set_external_store must be called before grouping externally"))?;
+        let key_of = group_by_key_fn(group_by)?;
+
+        let mut groups: std::collections::HashMap<String, (f64, usize)> = std::collections::HashMap::new();
+        let mut accumulate = |rows: &[DataRow]| {
+            for row in rows {
+                let entry = groups.entry(key_of(row)).or_insert((0.0, 0));
+                entry.0 += row.sales;
+                entry.1 += 1;
+            }
+        };
+        accumulate(&self.data);
+        self.spill.for_each_partition(&store, |rows| accumulate(rows)).await?;
+
+        let results: Vec<serde_json::Value> = groups
+            .into_iter()
+            .map(|(key, (total, count))| {
+                serde_json::json!({
+                    (group_by): key,
+                    "total_sales": total,
+                    "count": count,
+                    "avg_sales": total / count as f64,
+                })
+            })
+            .collect();
+
+        serde_wasm_bindgen::to_value(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
     /// Load data from JSON
@@ -55,35 +271,70 @@ impl QueryEngine {
         Ok(())
     }
 
-    /// Execute a simple query (simplified for demo)
-    /// In production, use a proper SQL parser like sqlparser-rs
+    /// Load data from an Arrow IPC stream, mapping columns by name and
+    /// avoiding the JSON parse/allocate cost of `load_data`.
+    pub fn load_arrow_ipc(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let reader = StreamReader::try_new(Cursor::new(bytes), None)
+            .map_err(|e| JsValue::from_str(&format!("Failed to read Arrow IPC stream: {}", e)))?;
+
+        let mut rows = Vec::new();
+        for batch in reader {
+            let batch = batch
+                .map_err(|e| JsValue::from_str(&format!("Failed to decode Arrow batch: {}", e)))?;
+            rows.extend(rows_from_batch(&batch)?);
+        }
+
+        self.data = rows;
+        Ok(())
+    }
+
+    /// Load data from a Parquet file's bytes, mapping columns by name. Row
+    /// groups are read one at a time via a fresh reader scoped to just that
+    /// group (`Bytes` clones are cheap refcounted views, not copies), which
+    /// keeps the decoded Arrow batches bounded by a single row group and sets
+    /// up a future streaming load mode. The input bytes are copied once, and
+    /// the rows of every row group are collected into `data`.
+    pub fn load_parquet(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let bytes = bytes::Bytes::copy_from_slice(bytes);
+        let num_row_groups = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to open Parquet file: {}", e)))?
+            .metadata()
+            .num_row_groups();
+
+        let mut rows = Vec::new();
+        for row_group in 0..num_row_groups {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+                .map_err(|e| JsValue::from_str(&format!("Failed to open Parquet file: {}", e)))?
+                .with_row_groups(vec![row_group])
+                .build()
+                .map_err(|e| JsValue::from_str(&format!("Failed to build Parquet reader: {}", e)))?;
+
+            for batch in reader {
+                let batch = batch.map_err(|e| {
+                    JsValue::from_str(&format!("Failed to decode Parquet row group: {}", e))
+                })?;
+                rows.extend(rows_from_batch(&batch)?);
+            }
+        }
+
+        self.data = rows;
+        Ok(())
+    }
+
+    /// Parse `query` into a logical plan, optimize it (pushing filters below
+    /// aggregates, pruning unreferenced scan columns), and execute it.
+    /// Supports `SELECT ... WHERE ... GROUP BY ... ORDER BY ... LIMIT ...`
+    /// with compound `AND`/`OR` predicates, and `UNION`/`UNION ALL` of two
+    /// such selects with type coercion between their schemas.
     pub fn execute_query(&self, query: &str) -> Result<JsValue, JsValue> {
-        let query_lower = query.to_lowercase();
         let start_time = js_sys::Date::now();
 
-        let result_rows: Vec<serde_json::Value> = if query_lower.contains("where sales >") {
-            // Simple filter query
-            let threshold = self.extract_number_from_query(&query_lower, "where sales >")
-                .unwrap_or(0.0);
-            
-            self.data
-                .iter()
-                .filter(|row| row.sales > threshold)
-                .map(|row| serde_json::to_value(row).unwrap())
-                .collect()
-        } else if query_lower.contains("group by category") {
-            // Aggregate by category
-            self.aggregate_by_category()
-        } else if query_lower.contains("group by region") {
-            // Aggregate by region
-            self.aggregate_by_region()
-        } else {
-            // Default: return all data
-            self.data
-                .iter()
-                .map(|row| serde_json::to_value(row).unwrap())
-                .collect()
-        };
+        let logical_plan = parse_plan(query).map_err(|e| JsValue::from_str(&e))?;
+        let optimized_plan = optimize(logical_plan);
+
+        let mut stats = Vec::new();
+        let result_rows = plan::execute(&optimized_plan, &self.data, &mut stats)
+            .map_err(|e| JsValue::from_str(&e))?;
 
         let execution_time = js_sys::Date::now() - start_time;
 
@@ -97,110 +348,42 @@ impl QueryEngine {
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
-    /// Generate query execution plan
+    /// Parse and optimize `query`, returning the actual optimized plan tree
+    /// with per-node cost and estimated row counts (no execution happens).
     pub fn explain_query(&self, query: &str) -> Result<JsValue, JsValue> {
-        let query_lower = query.to_lowercase();
-        let mut steps = Vec::new();
-
-        // Parse query and generate plan
-        steps.push(QueryPlanStep {
-            operation: "Parse SQL".to_string(),
-            cost: 0.1,
-            rows_estimated: 0,
-        });
-
-        steps.push(QueryPlanStep {
-            operation: "Validate Schema".to_string(),
-            cost: 0.1,
-            rows_estimated: 0,
-        });
-
-        if query_lower.contains("where") {
-            steps.push(QueryPlanStep {
-                operation: "Filter Scan".to_string(),
-                cost: (self.data.len() as f64) * 0.001,
-                rows_estimated: self.data.len() / 2,
-            });
-        }
-
-        if query_lower.contains("group by") {
-            steps.push(QueryPlanStep {
-                operation: "Hash Aggregate".to_string(),
-                cost: (self.data.len() as f64) * 0.002,
-                rows_estimated: 10,
-            });
-        }
-
-        if query_lower.contains("order by") {
-            steps.push(QueryPlanStep {
-                operation: "Sort".to_string(),
-                cost: (self.data.len() as f64) * 0.003,
-                rows_estimated: 10,
-            });
-        }
+        let logical_plan = parse_plan(query).map_err(|e| JsValue::from_str(&e))?;
+        let optimized_plan = optimize(logical_plan);
+        let tree = plan::explain_tree(&optimized_plan, self.data.len());
 
-        serde_wasm_bindgen::to_value(&steps)
+        serde_wasm_bindgen::to_value(&tree)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
-    // Helper methods
-    fn extract_number_from_query(&self, query: &str, after: &str) -> Option<f64> {
-        query
-            .split(after)
-            .nth(1)?
-            .split_whitespace()
-            .next()?
-            .parse()
-            .ok()
-    }
+    /// Like `explain_query`, but actually executes the plan and reports
+    /// real per-operator row counts and timings instead of estimates.
+    pub fn explain_analyze(&self, query: &str) -> Result<JsValue, JsValue> {
+        let logical_plan = parse_plan(query).map_err(|e| JsValue::from_str(&e))?;
+        let optimized_plan = optimize(logical_plan);
 
-    fn aggregate_by_category(&self) -> Vec<serde_json::Value> {
-        let mut agg: HashMap<String, (f64, usize)> = HashMap::new();
-        
-        for row in &self.data {
-            let entry = agg.entry(row.category.clone()).or_insert((0.0, 0));
-            entry.0 += row.sales;
-            entry.1 += 1;
-        }
+        let mut stats = Vec::new();
+        plan::execute(&optimized_plan, &self.data, &mut stats).map_err(|e| JsValue::from_str(&e))?;
 
-        agg.into_iter()
-            .map(|(category, (total, count))| {
-                serde_json::json!({
-                    "category": category,
-                    "total_sales": total,
-                    "count": count,
-                    "avg_sales": total / count as f64
-                })
-            })
-            .collect()
-    }
-
-    fn aggregate_by_region(&self) -> Vec<serde_json::Value> {
-        let mut agg: HashMap<String, (f64, usize)> = HashMap::new();
-        
-        for row in &self.data {
-            let entry = agg.entry(row.region.clone()).or_insert((0.0, 0));
-            entry.0 += row.sales;
-            entry.1 += 1;
-        }
-
-        agg.into_iter()
-            .map(|(region, (total, count))| {
-                serde_json::json!({
-                    "region": region,
-                    "total_sales": total,
-                    "count": count,
-                    "avg_sales": total / count as f64
-                })
-            })
-            .collect()
+        serde_wasm_bindgen::to_value(&stats)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 }
 
 // Extension points:
-// 1. Integrate sqlparser-rs for full SQL support
-// 2. Implement query optimization rules
-// 3. Add index support for faster lookups
-// 4. Implement join algorithms (nested loop, hash join, merge join)
-// 5. Add query result caching
-// 6. Implement EXPLAIN ANALYZE for actual execution stats
+// 1. Add index support for faster lookups
+// 2. Implement join algorithms (nested loop, hash join, merge join)
+// 3. Add query result caching
+// 4. Wire `execute_query`/`explain_query` themselves to run against spilled
+//    partitions instead of requiring `self.data` to fit in memory: today
+//    `filter_by_sales_external`/`group_by_external` are genuine standalone
+//    out-of-core operations (`scan_external` still reassembles the full
+//    table), but none of them is the general plan interpreter from
+//    `plan::execute`. An external (partition-and-merge) `ORDER BY` fallback
+//    is also still missing — unlike grouping, merging sorted runs from
+//    spilled partitions needs either multiple merge passes or turning
+//    `plan::execute` itself into a partition-streaming interpreter, which is
+//    a bigger change than this pass's scope
@@ -0,0 +1,817 @@
+//! Logical query plan for [`crate::QueryEngine`]: a small set of typed
+//! operators built from a `sqlparser` AST, an optimizer pass that pushes
+//! filters below aggregates and prunes unreferenced columns, and an
+//! interpreter that walks the optimized plan to produce rows.
+//!
+//! The pipeline is: `parse_plan` (naive plan in clause order, via
+//! `build_from_query`) -> `optimize` -> `execute` (which also records
+//! per-operator stats).
+
+use std::collections::HashSet;
+
+use sqlparser::ast::{
+    BinaryOperator, Expr, GroupByExpr, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    SetOperator, SetQuantifier, Statement, Value as SqlValue,
+};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+
+use crate::DataRow;
+
+/// A typed scalar pulled out of a parsed SQL literal or a row's column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn from_binary_op(op: &BinaryOperator) -> Option<Self> {
+        match op {
+            BinaryOperator::Lt => Some(Self::Lt),
+            BinaryOperator::LtEq => Some(Self::Le),
+            BinaryOperator::Eq => Some(Self::Eq),
+            BinaryOperator::NotEq => Some(Self::Ne),
+            BinaryOperator::Gt => Some(Self::Gt),
+            BinaryOperator::GtEq => Some(Self::Ge),
+            _ => None,
+        }
+    }
+}
+
+/// A `WHERE`/compound predicate, built from `AND`/`OR` of simple column
+/// comparisons.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    Compare {
+        column: String,
+        op: CompareOp,
+        value: Scalar,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    /// Columns this predicate reads, used for column pruning.
+    fn referenced_columns(&self, out: &mut HashSet<String>) {
+        match self {
+            Predicate::Compare { column, .. } => {
+                out.insert(column.clone());
+            }
+            Predicate::And(a, b) | Predicate::Or(a, b) => {
+                a.referenced_columns(out);
+                b.referenced_columns(out);
+            }
+        }
+    }
+
+    fn matches(&self, row: &serde_json::Value) -> bool {
+        match self {
+            Predicate::Compare { column, op, value } => {
+                let Some(field) = row.get(column) else {
+                    return false;
+                };
+                compare(field, *op, value)
+            }
+            Predicate::And(a, b) => a.matches(row) && b.matches(row),
+            Predicate::Or(a, b) => a.matches(row) || b.matches(row),
+        }
+    }
+}
+
+fn compare(field: &serde_json::Value, op: CompareOp, value: &Scalar) -> bool {
+    match value {
+        Scalar::Number(n) => {
+            let Some(f) = field.as_f64() else { return false };
+            match op {
+                CompareOp::Lt => f < *n,
+                CompareOp::Le => f <= *n,
+                CompareOp::Eq => f == *n,
+                CompareOp::Ne => f != *n,
+                CompareOp::Gt => f > *n,
+                CompareOp::Ge => f >= *n,
+            }
+        }
+        Scalar::Text(s) => {
+            let Some(text) = field.as_str() else { return false };
+            match op {
+                CompareOp::Eq => text == s,
+                CompareOp::Ne => text != s,
+                // Ordering comparisons on text aren't supported by this demo
+                // engine; treat them as non-matching rather than guessing.
+                _ => false,
+            }
+        }
+    }
+}
+
+/// A node in the logical plan. `columns` on `Scan` is the set of source
+/// columns the optimizer determined are actually read; everything else
+/// composes by wrapping an `input`.
+#[derive(Debug, Clone)]
+pub enum PlanNode {
+    Scan {
+        columns: Vec<String>,
+    },
+    Filter {
+        predicate: Predicate,
+        input: Box<PlanNode>,
+    },
+    Aggregate {
+        group_by: String,
+        input: Box<PlanNode>,
+    },
+    Project {
+        columns: Vec<String>,
+        input: Box<PlanNode>,
+    },
+    Sort {
+        column: String,
+        ascending: bool,
+        input: Box<PlanNode>,
+    },
+    Limit {
+        n: usize,
+        input: Box<PlanNode>,
+    },
+    Union {
+        left: Box<PlanNode>,
+        right: Box<PlanNode>,
+        all: bool,
+    },
+}
+
+const ALL_COLUMNS: [&str; 4] = ["id", "category", "sales", "region"];
+
+/// Parse `sql` and build the naive (pre-optimization) logical plan. Only
+/// `SELECT ... [WHERE] [GROUP BY] [ORDER BY] [LIMIT]`, optionally combined
+/// with `UNION`/`UNION ALL` of exactly two such selects, is supported.
+pub fn parse_plan(sql: &str) -> Result<PlanNode, String> {
+    let statements =
+        Parser::parse_sql(&GenericDialect {}, sql).map_err(|e| format!("SQL parse error: {}", e))?;
+
+    let statement = statements
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No statement found".to_string())?;
+
+    let query = match statement {
+        Statement::Query(query) => query,
+        other => return Err(format!("Unsupported statement: {:?}", other)),
+    };
+
+    build_from_query(&query)
+}
+
+fn build_from_query(query: &Query) -> Result<PlanNode, String> {
+    let plan = match query.body.as_ref() {
+        SetExpr::Select(select) => {
+            // `ORDER BY` is allowed to reference a column that isn't in the
+            // SELECT list (e.g. `SELECT category FROM t ORDER BY sales`), so
+            // `Sort` must see the pre-projection row; `Project` is applied
+            // afterwards, above `Sort`, not folded into `build_from_select`.
+            let (pre_project, project) = build_from_select(select)?;
+            let sorted = if !query.order_by.is_empty() {
+                wrap_sort(pre_project, &query.order_by)?
+            } else {
+                pre_project
+            };
+            apply_project(sorted, project)
+        }
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            set_quantifier,
+            left,
+            right,
+        } => {
+            // Each UNION operand must already be projected down to its own
+            // output schema before the branches are combined, so `ORDER BY`
+            // here (which applies to the combined result) sorts by the
+            // union's already-projected columns.
+            let left_plan = build_select_operand(left)?;
+            let right_plan = build_select_operand(right)?;
+            let unioned = PlanNode::Union {
+                left: Box::new(left_plan),
+                right: Box::new(right_plan),
+                all: matches!(set_quantifier, SetQuantifier::All),
+            };
+            if !query.order_by.is_empty() {
+                wrap_sort(unioned, &query.order_by)?
+            } else {
+                unioned
+            }
+        }
+        other => return Err(format!("Unsupported query body: {:?}", other)),
+    };
+
+    Ok(match &query.limit {
+        Some(Expr::Value(SqlValue::Number(n, _))) => PlanNode::Limit {
+            n: n.parse().map_err(|_| "Invalid LIMIT value".to_string())?,
+            input: Box::new(plan),
+        },
+        Some(other) => return Err(format!("Unsupported LIMIT expression: {:?}", other)),
+        None => plan,
+    })
+}
+
+fn build_select_operand(set_expr: &SetExpr) -> Result<PlanNode, String> {
+    match set_expr {
+        SetExpr::Select(s) => {
+            let (pre_project, project) = build_from_select(s)?;
+            Ok(apply_project(pre_project, project))
+        }
+        _ => Err("UNION operands must be simple SELECTs".to_string()),
+    }
+}
+
+/// Wrap `plan` in a `Project` node for `columns`, or return it unchanged if
+/// there's nothing to project (a bare `SELECT *` over a `GROUP BY`, where the
+/// aggregate's own output columns are already the final shape).
+fn apply_project(plan: PlanNode, columns: Option<Vec<String>>) -> PlanNode {
+    match columns {
+        Some(columns) => PlanNode::Project {
+            columns,
+            input: Box::new(plan),
+        },
+        None => plan,
+    }
+}
+
+fn wrap_sort(plan: PlanNode, order_by: &[OrderByExpr]) -> Result<PlanNode, String> {
+    let order = order_by
+        .first()
+        .ok_or_else(|| "ORDER BY with no expressions".to_string())?;
+    let column = column_name(&order.expr)?;
+    Ok(PlanNode::Sort {
+        column,
+        ascending: order.asc.unwrap_or(true),
+        input: Box::new(plan),
+    })
+}
+
+/// Build the *naive* pre-projection plan for a single `SELECT`, in
+/// clause-literal order: `Scan -> Aggregate -> Filter`, plus the columns
+/// that should eventually be projected (applied by the caller, above `Sort`
+/// and below `Limit` — see [`build_from_query`]). Note `Filter` is deliberately left
+/// *above* `Aggregate` here, mirroring the textual WHERE/GROUP BY order;
+/// [`optimize`] is responsible for pushing it back down to where the source
+/// rows are, which is where it actually belongs.
+fn build_from_select(select: &Select) -> Result<(PlanNode, Option<Vec<String>>), String> {
+    let mut plan = PlanNode::Scan {
+        columns: ALL_COLUMNS.iter().map(|c| c.to_string()).collect(),
+    };
+
+    let group_by = match &select.group_by {
+        GroupByExpr::Expressions(exprs) if !exprs.is_empty() => Some(column_name(&exprs[0])?),
+        _ => None,
+    };
+    let has_group_by = group_by.is_some();
+    if let Some(group_by) = group_by {
+        plan = PlanNode::Aggregate {
+            group_by,
+            input: Box::new(plan),
+        };
+    }
+
+    if let Some(selection) = &select.selection {
+        plan = PlanNode::Filter {
+            predicate: build_predicate(selection)?,
+            input: Box::new(plan),
+        };
+    }
+
+    let project = if !is_select_star(&select.projection) {
+        Some(
+            select
+                .projection
+                .iter()
+                .map(projection_column_name)
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    } else if !has_group_by {
+        // A bare `SELECT *` has no explicit column list, so nothing
+        // downstream would otherwise mark source columns as referenced and
+        // `prune_scan_columns` would collapse the Scan to a single column.
+        // Make the "select everything" intent explicit instead (skipped
+        // when there's a `GROUP BY`, since the aggregate's own output
+        // columns are what `SELECT *` means there, not the raw source
+        // columns).
+        Some(ALL_COLUMNS.iter().map(|c| c.to_string()).collect())
+    } else {
+        None
+    };
+
+    Ok((plan, project))
+}
+
+fn is_select_star(projection: &[SelectItem]) -> bool {
+    matches!(projection, [SelectItem::Wildcard(_)])
+}
+
+fn projection_column_name(item: &SelectItem) -> Result<String, String> {
+    match item {
+        SelectItem::UnnamedExpr(expr) => column_name(expr),
+        SelectItem::ExprWithAlias { expr, .. } => column_name(expr),
+        other => Err(format!("Unsupported projection item: {:?}", other)),
+    }
+}
+
+fn column_name(expr: &Expr) -> Result<String, String> {
+    match expr {
+        Expr::Identifier(ident) => Ok(ident.value.clone()),
+        Expr::CompoundIdentifier(parts) => Ok(parts
+            .last()
+            .map(|p| p.value.clone())
+            .unwrap_or_default()),
+        other => Err(format!("Expected a column reference, found: {:?}", other)),
+    }
+}
+
+fn build_predicate(expr: &Expr) -> Result<Predicate, String> {
+    match expr {
+        Expr::BinaryOp { left, op, right } => match op {
+            BinaryOperator::And => Ok(Predicate::And(
+                Box::new(build_predicate(left)?),
+                Box::new(build_predicate(right)?),
+            )),
+            BinaryOperator::Or => Ok(Predicate::Or(
+                Box::new(build_predicate(left)?),
+                Box::new(build_predicate(right)?),
+            )),
+            _ => {
+                let column = column_name(left)?;
+                let op = CompareOp::from_binary_op(op)
+                    .ok_or_else(|| format!("Unsupported comparison operator: {:?}", op))?;
+                let value = literal_value(right)?;
+                Ok(Predicate::Compare { column, op, value })
+            }
+        },
+        Expr::Nested(inner) => build_predicate(inner),
+        other => Err(format!("Unsupported WHERE expression: {:?}", other)),
+    }
+}
+
+fn literal_value(expr: &Expr) -> Result<Scalar, String> {
+    match expr {
+        Expr::Value(SqlValue::Number(n, _)) => {
+            n.parse().map(Scalar::Number).map_err(|_| format!("Invalid numeric literal: {}", n))
+        }
+        Expr::Value(SqlValue::SingleQuotedString(s)) => Ok(Scalar::Text(s.clone())),
+        Expr::UnaryOp { op, expr } if matches!(op, sqlparser::ast::UnaryOperator::Minus) => {
+            match literal_value(expr)? {
+                Scalar::Number(n) => Ok(Scalar::Number(-n)),
+                Scalar::Text(_) => Err("Cannot negate a string literal".to_string()),
+            }
+        }
+        other => Err(format!("Unsupported literal: {:?}", other)),
+    }
+}
+
+/// Rewrite the naive plan: push `Filter` below `Aggregate` when every
+/// column it reads is a raw source column (never an aggregate output), and
+/// prune each `Scan` down to only the columns actually referenced anywhere
+/// in the plan (one set shared by both sides of a `UNION`).
+pub fn optimize(plan: PlanNode) -> PlanNode {
+    let pushed = push_filters_below_aggregate(plan);
+    let mut referenced = HashSet::new();
+    collect_referenced_columns(&pushed, &mut referenced);
+    prune_scan_columns(pushed, &referenced)
+}
+
+fn push_filters_below_aggregate(plan: PlanNode) -> PlanNode {
+    match plan {
+        PlanNode::Filter { predicate, input } => {
+            let input = push_filters_below_aggregate(*input);
+            match input {
+                PlanNode::Aggregate {
+                    group_by,
+                    input: agg_input,
+                } if predicate_is_pre_aggregate(&predicate) => PlanNode::Aggregate {
+                    group_by,
+                    input: Box::new(PlanNode::Filter {
+                        predicate,
+                        input: agg_input,
+                    }),
+                },
+                other => PlanNode::Filter {
+                    predicate,
+                    input: Box::new(other),
+                },
+            }
+        }
+        PlanNode::Aggregate { group_by, input } => PlanNode::Aggregate {
+            group_by,
+            input: Box::new(push_filters_below_aggregate(*input)),
+        },
+        PlanNode::Project { columns, input } => PlanNode::Project {
+            columns,
+            input: Box::new(push_filters_below_aggregate(*input)),
+        },
+        PlanNode::Sort {
+            column,
+            ascending,
+            input,
+        } => PlanNode::Sort {
+            column,
+            ascending,
+            input: Box::new(push_filters_below_aggregate(*input)),
+        },
+        PlanNode::Limit { n, input } => PlanNode::Limit {
+            n,
+            input: Box::new(push_filters_below_aggregate(*input)),
+        },
+        PlanNode::Union { left, right, all } => PlanNode::Union {
+            left: Box::new(push_filters_below_aggregate(*left)),
+            right: Box::new(push_filters_below_aggregate(*right)),
+            all,
+        },
+        scan @ PlanNode::Scan { .. } => scan,
+    }
+}
+
+fn predicate_is_pre_aggregate(predicate: &Predicate) -> bool {
+    let mut columns = HashSet::new();
+    predicate.referenced_columns(&mut columns);
+    columns.iter().all(|c| ALL_COLUMNS.contains(&c.as_str()))
+}
+
+fn collect_referenced_columns(plan: &PlanNode, out: &mut HashSet<String>) {
+    match plan {
+        PlanNode::Scan { .. } => {}
+        PlanNode::Filter { predicate, input } => {
+            predicate.referenced_columns(out);
+            collect_referenced_columns(input, out);
+        }
+        PlanNode::Aggregate { group_by, input } => {
+            out.insert(group_by.clone());
+            // `aggregate_rows` always reads the `sales` measure regardless
+            // of what's projected, so pruning must never drop it.
+            out.insert("sales".to_string());
+            collect_referenced_columns(input, out);
+        }
+        PlanNode::Project { columns, input } => {
+            out.extend(columns.iter().cloned());
+            collect_referenced_columns(input, out);
+        }
+        PlanNode::Sort { column, input, .. } => {
+            out.insert(column.clone());
+            collect_referenced_columns(input, out);
+        }
+        PlanNode::Limit { input, .. } => collect_referenced_columns(input, out),
+        PlanNode::Union { left, right, .. } => {
+            collect_referenced_columns(left, out);
+            collect_referenced_columns(right, out);
+        }
+    }
+}
+
+fn prune_scan_columns(plan: PlanNode, referenced: &HashSet<String>) -> PlanNode {
+    match plan {
+        PlanNode::Scan { columns } => {
+            let mut pruned: Vec<String> = columns
+                .into_iter()
+                .filter(|c| referenced.contains(c))
+                .collect();
+            if pruned.is_empty() {
+                // Always need at least one column to know the row count.
+                pruned.push(ALL_COLUMNS[0].to_string());
+            }
+            PlanNode::Scan { columns: pruned }
+        }
+        PlanNode::Filter { predicate, input } => PlanNode::Filter {
+            predicate,
+            input: Box::new(prune_scan_columns(*input, referenced)),
+        },
+        PlanNode::Aggregate { group_by, input } => PlanNode::Aggregate {
+            group_by,
+            input: Box::new(prune_scan_columns(*input, referenced)),
+        },
+        PlanNode::Project { columns, input } => PlanNode::Project {
+            columns,
+            input: Box::new(prune_scan_columns(*input, referenced)),
+        },
+        PlanNode::Sort {
+            column,
+            ascending,
+            input,
+        } => PlanNode::Sort {
+            column,
+            ascending,
+            input: Box::new(prune_scan_columns(*input, referenced)),
+        },
+        PlanNode::Limit { n, input } => PlanNode::Limit {
+            n,
+            input: Box::new(prune_scan_columns(*input, referenced)),
+        },
+        PlanNode::Union { left, right, all } => PlanNode::Union {
+            left: Box::new(prune_scan_columns(*left, referenced)),
+            right: Box::new(prune_scan_columns(*right, referenced)),
+            all,
+        },
+    }
+}
+
+/// Per-operator row counts and timings recorded while executing a plan,
+/// used by `QueryEngine::explain_analyze`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OperatorStats {
+    pub operation: String,
+    pub rows_in: usize,
+    pub rows_out: usize,
+    pub duration_ms: f64,
+}
+
+fn row_to_json(row: &DataRow, columns: &[String]) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    for column in columns {
+        let value = match column.as_str() {
+            "id" => serde_json::json!(row.id),
+            "category" => serde_json::json!(row.category),
+            "sales" => serde_json::json!(row.sales),
+            "region" => serde_json::json!(row.region),
+            _ => continue,
+        };
+        map.insert(column.clone(), value);
+    }
+    serde_json::Value::Object(map)
+}
+
+fn aggregate_rows(rows: Vec<serde_json::Value>, group_by: &str) -> Vec<serde_json::Value> {
+    use std::collections::HashMap;
+
+    let mut groups: HashMap<String, (f64, usize)> = HashMap::new();
+    for row in &rows {
+        let key = row
+            .get(group_by)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let sales = row.get("sales").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let entry = groups.entry(key).or_insert((0.0, 0));
+        entry.0 += sales;
+        entry.1 += 1;
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, (total, count))| {
+            serde_json::json!({
+                group_by: key,
+                "total_sales": total,
+                "count": count,
+                "avg_sales": total / count as f64,
+            })
+        })
+        .collect()
+}
+
+fn project_rows(rows: Vec<serde_json::Value>, columns: &[String]) -> Vec<serde_json::Value> {
+    rows.into_iter()
+        .map(|row| {
+            let mut map = serde_json::Map::new();
+            for column in columns {
+                if let Some(value) = row.get(column) {
+                    map.insert(column.clone(), value.clone());
+                }
+            }
+            serde_json::Value::Object(map)
+        })
+        .collect()
+}
+
+fn sort_rows(mut rows: Vec<serde_json::Value>, column: &str, ascending: bool) -> Vec<serde_json::Value> {
+    rows.sort_by(|a, b| {
+        let ordering = match (a.get(column), b.get(column)) {
+            (Some(x), Some(y)) if x.is_number() && y.is_number() => x
+                .as_f64()
+                .unwrap_or(0.0)
+                .partial_cmp(&y.as_f64().unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal),
+            (Some(x), Some(y)) => x
+                .as_str()
+                .unwrap_or_default()
+                .cmp(y.as_str().unwrap_or_default()),
+            _ => std::cmp::Ordering::Equal,
+        };
+        if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+    rows
+}
+
+/// Check the two `UNION` inputs' schemas for a common type per column name:
+/// integer and float JSON numbers both count as numeric, a column seen as
+/// both numeric and text is an error, and nulls are ignored. Values are
+/// passed through unchanged, in left-then-right order.
+fn coerce_union_schema(
+    left: Vec<serde_json::Value>,
+    right: Vec<serde_json::Value>,
+) -> Result<Vec<serde_json::Value>, String> {
+    use std::collections::HashMap;
+
+    let mut column_is_numeric: HashMap<String, bool> = HashMap::new();
+    for row in left.iter().chain(right.iter()) {
+        let Some(obj) = row.as_object() else { continue };
+        for (key, value) in obj {
+            if value.is_null() {
+                continue;
+            }
+            let is_numeric = value.is_number();
+            match column_is_numeric.get(key) {
+                Some(existing) if *existing != is_numeric => {
+                    return Err(format!(
+                        "UNION type mismatch in column '{}': both numeric and text values present",
+                        key
+                    ));
+                }
+                _ => {
+                    column_is_numeric.insert(key.clone(), is_numeric);
+                }
+            }
+        }
+    }
+
+    Ok(left.into_iter().chain(right).collect())
+}
+
+fn dedup_rows(rows: Vec<serde_json::Value>) -> Vec<serde_json::Value> {
+    let mut seen = HashSet::new();
+    rows.into_iter()
+        .filter(|row| seen.insert(row.to_string()))
+        .collect()
+}
+
+/// Execute `plan` over `source`, recording per-operator row counts and
+/// timings into `stats` (used by `explain_analyze`; pass an empty `Vec` and
+/// ignore it for plain execution).
+pub fn execute(
+    plan: &PlanNode,
+    source: &[DataRow],
+    stats: &mut Vec<OperatorStats>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let start = js_sys::Date::now();
+    let (operation, rows_in, rows) = match plan {
+        PlanNode::Scan { columns } => (
+            "Scan".to_string(),
+            source.len(),
+            source.iter().map(|row| row_to_json(row, columns)).collect(),
+        ),
+        PlanNode::Filter { predicate, input } => {
+            let input_rows = execute(input, source, stats)?;
+            let rows_in = input_rows.len();
+            (
+                "Filter".to_string(),
+                rows_in,
+                input_rows
+                    .into_iter()
+                    .filter(|row| predicate.matches(row))
+                    .collect(),
+            )
+        }
+        PlanNode::Aggregate { group_by, input } => {
+            let input_rows = execute(input, source, stats)?;
+            let rows_in = input_rows.len();
+            ("Hash Aggregate".to_string(), rows_in, aggregate_rows(input_rows, group_by))
+        }
+        PlanNode::Project { columns, input } => {
+            let input_rows = execute(input, source, stats)?;
+            let rows_in = input_rows.len();
+            ("Project".to_string(), rows_in, project_rows(input_rows, columns))
+        }
+        PlanNode::Sort {
+            column,
+            ascending,
+            input,
+        } => {
+            let input_rows = execute(input, source, stats)?;
+            let rows_in = input_rows.len();
+            ("Sort".to_string(), rows_in, sort_rows(input_rows, column, *ascending))
+        }
+        PlanNode::Limit { n, input } => {
+            let mut input_rows = execute(input, source, stats)?;
+            let rows_in = input_rows.len();
+            input_rows.truncate(*n);
+            ("Limit".to_string(), rows_in, input_rows)
+        }
+        PlanNode::Union { left, right, all } => {
+            let left_rows = execute(left, source, stats)?;
+            let right_rows = execute(right, source, stats)?;
+            let rows_in = left_rows.len() + right_rows.len();
+            let mut combined = coerce_union_schema(left_rows, right_rows)?;
+            if !all {
+                combined = dedup_rows(combined);
+            }
+            (
+                if *all { "Union All".to_string() } else { "Union".to_string() },
+                rows_in,
+                combined,
+            )
+        }
+    };
+
+    let duration_ms = js_sys::Date::now() - start;
+    stats.push(OperatorStats {
+        operation,
+        rows_in,
+        rows_out: rows.len(),
+        duration_ms,
+    });
+
+    Ok(rows)
+}
+
+/// A node of the optimized plan tree with its estimated cost, for
+/// `QueryEngine::explain_query`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlanTreeNode {
+    pub operation: String,
+    pub cost: f64,
+    pub rows_estimated: usize,
+    pub children: Vec<PlanTreeNode>,
+}
+
+/// Build an explain-friendly cost tree from the optimized plan. Costs are a
+/// simple per-row estimate; `rows_estimated` assumes a filter halves its
+/// input and a group-by collapses to at most 10 groups; these are fixed
+/// heuristics, not derived from the loaded data.
+pub fn explain_tree(plan: &PlanNode, total_rows: usize) -> PlanTreeNode {
+    match plan {
+        PlanNode::Scan { columns } => PlanTreeNode {
+            operation: format!("Scan ({})", columns.join(", ")),
+            cost: total_rows as f64 * 0.0001 * columns.len().max(1) as f64,
+            rows_estimated: total_rows,
+            children: vec![],
+        },
+        PlanNode::Filter { input, .. } => {
+            let child = explain_tree(input, total_rows);
+            let rows_estimated = child.rows_estimated / 2;
+            PlanTreeNode {
+                operation: "Filter Scan".to_string(),
+                cost: child.rows_estimated as f64 * 0.001,
+                rows_estimated,
+                children: vec![child],
+            }
+        }
+        PlanNode::Aggregate { group_by, input } => {
+            let child = explain_tree(input, total_rows);
+            PlanTreeNode {
+                operation: format!("Hash Aggregate (by {})", group_by),
+                cost: child.rows_estimated as f64 * 0.002,
+                rows_estimated: 10.min(child.rows_estimated),
+                children: vec![child],
+            }
+        }
+        PlanNode::Project { columns, input } => {
+            let child = explain_tree(input, total_rows);
+            PlanTreeNode {
+                operation: format!("Project ({})", columns.join(", ")),
+                cost: child.rows_estimated as f64 * 0.0005,
+                rows_estimated: child.rows_estimated,
+                children: vec![child],
+            }
+        }
+        PlanNode::Sort { column, input, .. } => {
+            let child = explain_tree(input, total_rows);
+            PlanTreeNode {
+                operation: format!("Sort (by {})", column),
+                cost: child.rows_estimated as f64 * 0.003,
+                rows_estimated: child.rows_estimated,
+                children: vec![child],
+            }
+        }
+        PlanNode::Limit { n, input } => {
+            let child = explain_tree(input, total_rows);
+            PlanTreeNode {
+                operation: format!("Limit ({})", n),
+                cost: 0.01,
+                rows_estimated: child.rows_estimated.min(*n),
+                children: vec![child],
+            }
+        }
+        PlanNode::Union { left, right, all } => {
+            let left_child = explain_tree(left, total_rows);
+            let right_child = explain_tree(right, total_rows);
+            let combined_rows = left_child.rows_estimated + right_child.rows_estimated;
+            PlanTreeNode {
+                operation: if *all { "Union All".to_string() } else { "Union".to_string() },
+                cost: left_child.cost + right_child.cost + combined_rows as f64 * 0.0005,
+                rows_estimated: combined_rows,
+                children: vec![left_child, right_child],
+            }
+        }
+    }
+}